@@ -1,6 +1,10 @@
 use wasm_bindgen::prelude::*;
 use js_sys::Float32Array;
 
+mod wav;
+mod loudness;
+mod resample;
+
 // --- 定数 ---
 
 /// RMS計算時の最小値。0による対数計算エラー（-Infinity）を防ぐための微小な値 (epsilon)
@@ -68,25 +72,29 @@ fn rms_to_dbfs(rms: f32) -> f32 {
 pub fn analyze_audio_rms(pcm_data: Float32Array, chunk_size_samples: usize) -> Float32Array {
     // JSのFloat32ArrayをRustのスライスに変換（コピーが発生するが、
     // WASM境界を越えるため、また安全なスライス操作のために許容する）
-    // 大容量データの場合、Web WorkerからWASMのメモリに直接書き込む高度な手法もあるが、
-    // まずは堅牢な実装とする。
+    // 大容量データを扱う場合は `allocInputBuffer` / `analyzeAudioRmsInPlace` による
+    // ゼロコピー経路を使うこと。
     let pcm_vec: Vec<f32> = pcm_data.to_vec();
 
-    // pcm_data.length() を使うよりもRust側で長さを取得する方が安全
-    let total_samples = pcm_vec.len();
+    // RustのVec<f32>からJSのFloat32Arrayに変換して返す (コピーが発生)
+    // `from` は効率的な変換（コピー）を提供します。
+    Float32Array::from(compute_dbfs_chunks(pcm_vec.as_slice(), chunk_size_samples).as_slice())
+}
+
+/// チャンク単位のdBFS計算の本体。`analyze_audio_rms`（コピー経由）と
+/// `analyze_audio_rms_in_place`（WASMメモリ直書き込み経由）、`wav::analyze_wav_rms`
+/// （WAVデコード経由）から共有される。
+pub(crate) fn compute_dbfs_chunks(pcm_slice: &[f32], chunk_size_samples: usize) -> Vec<f32> {
+    let total_samples = pcm_slice.len();
 
     if total_samples == 0 || chunk_size_samples == 0 {
-        // 空の配列を返す
-        return Float32Array::new_with_length(0);
+        return Vec::new();
     }
 
     // 結果を格納する配列のサイズを計算 (切り上げ)
     let num_chunks = (total_samples + chunk_size_samples - 1) / chunk_size_samples;
     let mut dbfs_results: Vec<f32> = Vec::with_capacity(num_chunks);
 
-    // pcm_vecを不変スライスとして取得
-    let pcm_slice = pcm_vec.as_slice();
-
     // 指定されたチャンクサイズでイテレーション
     // Rustの `chunks_exact` と `last` を組み合わせて効率的に処理
     let mut chunks_iter = pcm_slice.chunks_exact(chunk_size_samples);
@@ -103,7 +111,493 @@ pub fn analyze_audio_rms(pcm_data: Float32Array, chunk_size_samples: usize) -> F
         dbfs_results.push(rms_to_dbfs(rms));
     }
 
+    dbfs_results
+}
+
+#[wasm_bindgen(js_name = analyzeAudioRms)]
+pub fn analyze_audio_rms(pcm_data: Float32Array, chunk_size_samples: usize) -> Float32Array {
+    // JSのFloat32ArrayをRustのスライスに変換（コピーが発生するが、
+    // WASM境界を越えるため、また安全なスライス操作のために許容する）
+    // 大容量データを扱う場合は `allocInputBuffer` / `analyzeAudioRmsInPlace` による
+    // ゼロコピー経路を使うこと。
+    let pcm_vec: Vec<f32> = pcm_data.to_vec();
+
     // RustのVec<f32>からJSのFloat32Arrayに変換して返す (コピーが発生)
     // `from` は効率的な変換（コピー）を提供します。
-    Float32Array::from(dbfs_results.as_slice())
+    Float32Array::from(compute_dbfs_chunks(pcm_vec.as_slice(), chunk_size_samples).as_slice())
+}
+
+// --- ゼロコピー入力バッファ (WASMメモリ直接アクセス) ---
+
+/**
+ * WASM線形メモリ上に `len` 個のf32を確保し、そのポインタを返します。
+ *
+ * JS側はこのポインタと `len` を使って `new Float32Array(memory.buffer, ptr, len)` のように
+ * WASMメモリを直接参照する `Float32Array` ビューを作成できます。PCMサンプルをこのビューに
+ * 直接書き込めば、JS→WASM間でのコピーを発生させずにデータを受け渡せます。
+ * Web Workerでのストリーミング処理など、大容量データを繰り返し解析するケースで使うことを
+ * 想定しています。
+ *
+ * 確保したバッファは、使い終わったら必ず `freeInputBuffer` で解放してください。
+ *
+ * @param len - 確保するサンプル数
+ * @returns WASMメモリ上のバッファの先頭ポインタ
+ */
+#[wasm_bindgen(js_name = allocInputBuffer)]
+pub fn alloc_input_buffer(len: usize) -> *mut f32 {
+    let mut buffer: Vec<f32> = vec![0.0; len];
+    let ptr = buffer.as_mut_ptr();
+
+    // RustのアロケータにDropさせず、所有権をJS側（ポインタ経由）に預ける。
+    // 対応する `free_input_buffer` で `Vec::from_raw_parts` により再構築して解放する。
+    std::mem::forget(buffer);
+
+    ptr
+}
+
+/**
+ * `allocInputBuffer` で確保したバッファを解放します。
+ *
+ * @param ptr - `allocInputBuffer` が返したポインタ
+ * @param len - `allocInputBuffer` に渡したサンプル数と同じ値
+ */
+#[wasm_bindgen(js_name = freeInputBuffer)]
+// ポインタは`alloc_input_buffer`が返したWASM線形メモリ上のアドレスであり、
+// JS側から任意のポインタを渡せる形にはなるが、実際の安全性はJS側の呼び出し規律に依存する。
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn free_input_buffer(ptr: *mut f32, len: usize) {
+    // `alloc_input_buffer` が `forget` したVecをここで再構築し、
+    // スコープを抜ける際にDropさせることでメモリを解放する。
+    unsafe {
+        drop(Vec::from_raw_parts(ptr, len, len));
+    }
+}
+
+/**
+ * `allocInputBuffer` で確保したWASMメモリ上のバッファを直接読み取り、
+ * コピーなしでdBFS解析を行います。
+ *
+ * JS側であらかじめバッファにPCMサンプルを書き込んでおく必要があります。
+ * ロジックは `analyzeAudioRms` と共通です。
+ *
+ * @param ptr - 解析対象バッファの先頭ポインタ (`allocInputBuffer` の戻り値)
+ * @param len - バッファ内のサンプル数
+ * @param chunk_size_samples - 1チャンクあたりのサンプル数
+ * @returns 各チャンクのdBFS値が格納された Float32Array
+ */
+#[wasm_bindgen(js_name = analyzeAudioRmsInPlace)]
+// `free_input_buffer`と同様、ポインタの安全性はJS側の呼び出し規律に依存する。
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn analyze_audio_rms_in_place(ptr: *const f32, len: usize, chunk_size_samples: usize) -> Float32Array {
+    // JSが書き込んだWASMメモリ領域をコピーせずにスライスとして借用する。
+    // 呼び出し元は `ptr`/`len` が `alloc_input_buffer` で確保した有効な範囲であることを保証する必要がある。
+    let pcm_slice: &[f32] = unsafe { std::slice::from_raw_parts(ptr, len) };
+
+    Float32Array::from(compute_dbfs_chunks(pcm_slice, chunk_size_samples).as_slice())
+}
+
+// --- 無音区間検出 ---
+
+/// ヒステリシスの幅 (dB)。
+/// 「無音に入る」しきい値より「無音から抜ける」しきい値を
+/// この分だけ高くすることで、境界付近で値が上下してもチャンクの
+/// 判定がフリッカーしないようにする。
+const SILENCE_HYSTERESIS_DB: f32 = 3.0;
+
+/// 各チャンクが無音か否かの判定結果
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ChunkState {
+    Silent,
+    Active,
+}
+
+/// チャンクごとのdBFS値をヒステリシス付きで無音/有音に分類する。
+///
+/// `leave_silence_dbfs`（＝呼び出し元が指定したしきい値）を下回ったら無音に入り、
+/// `leave_silence_dbfs + SILENCE_HYSTERESIS_DB` を上回ったら無音から抜ける。
+/// 直前の状態を引き継ぐことで境界付近のフリッカーを防ぐ。
+fn classify_chunks_with_hysteresis(dbfs_values: &[f32], leave_silence_dbfs: f32) -> Vec<ChunkState> {
+    let enter_active_dbfs = leave_silence_dbfs + SILENCE_HYSTERESIS_DB;
+
+    let mut state = ChunkState::Active;
+    dbfs_values
+        .iter()
+        .map(|&dbfs| {
+            state = match state {
+                ChunkState::Active if dbfs <= leave_silence_dbfs => ChunkState::Silent,
+                ChunkState::Silent if dbfs > enter_active_dbfs => ChunkState::Active,
+                other => other,
+            };
+            state
+        })
+        .collect()
+}
+
+/// 連続する同じ状態のチャンクを1つの区間にまとめる。
+/// 戻り値は `(開始チャンク番号, 終了チャンク番号（排他的）, 状態)` のリスト。
+fn coalesce_runs(states: &[ChunkState]) -> Vec<(usize, usize, ChunkState)> {
+    let mut runs: Vec<(usize, usize, ChunkState)> = Vec::new();
+
+    for (i, &state) in states.iter().enumerate() {
+        match runs.last_mut() {
+            Some(last) if last.2 == state => last.1 = i + 1,
+            _ => runs.push((i, i + 1, state)),
+        }
+    }
+
+    runs
+}
+
+/// 短すぎる無音区間を有音として再分類し、前後の区間と1回の線形走査でまとめ直す。
+///
+/// `runs`はすでに`coalesce_runs`で交互のActive/Silentにまとめられているため、
+/// 各区間を前から順に見て「短い無音→有音に再分類」した上で、直前に積んだ区間と
+/// 状態が一致すれば結合していくだけで、区間全体を作り直すことなく最終結果が得られる。
+fn merge_short_silences(
+    runs: &[(usize, usize, ChunkState)],
+    chunk_to_sample: impl Fn(usize) -> usize,
+    sample_rate: f32,
+    min_silence_duration_secs: f32,
+) -> Vec<(usize, usize, ChunkState)> {
+    let mut merged: Vec<(usize, usize, ChunkState)> = Vec::with_capacity(runs.len());
+
+    for &(start, end, state) in runs {
+        let reclassified = if state == ChunkState::Silent {
+            let duration_secs = (chunk_to_sample(end) - chunk_to_sample(start)) as f32 / sample_rate;
+            if duration_secs < min_silence_duration_secs {
+                ChunkState::Active
+            } else {
+                ChunkState::Silent
+            }
+        } else {
+            ChunkState::Active
+        };
+
+        match merged.last_mut() {
+            Some(last) if last.2 == reclassified => last.1 = end,
+            _ => merged.push((start, end, reclassified)),
+        }
+    }
+
+    merged
+}
+
+/**
+ * PCMデータをチャンク単位で解析し、無音区間と有音区間に分割します。
+ *
+ * `analyze_audio_rms` と同じチャンク分割・RMS計算を内部で行った上で、
+ * 各チャンクを無音/有音に分類し、連続する同じ分類のチャンクを1つの区間としてまとめます。
+ * 短すぎる無音区間（`min_silence_duration_secs` 未満）は、単語間の小さな息継ぎなどと
+ * みなして有音として扱われ、前後の有音区間と結合されます。
+ *
+ * @param pcm_data - 音声データ (Float32Array)
+ * @param sample_rate - サンプリングレート (Hz)
+ * @param chunk_size_samples - 1チャンクあたりのサンプル数
+ * @param silence_threshold_dbfs - これ以下のdBFSを無音とみなすしきい値
+ * @param min_silence_duration_secs - この秒数未満の無音区間は有音として扱う
+ * @returns `[start_sample, end_sample, is_silence, ...]` の3つ組を並べたフラットな Float32Array
+ * （`is_silence` は無音なら1.0、有音なら0.0）
+ */
+#[wasm_bindgen(js_name = detectSilenceSegments)]
+pub fn detect_silence_segments(
+    pcm_data: Float32Array,
+    sample_rate: f32,
+    chunk_size_samples: usize,
+    silence_threshold_dbfs: f32,
+    min_silence_duration_secs: f32,
+) -> Float32Array {
+    let pcm_vec: Vec<f32> = pcm_data.to_vec();
+    let total_samples = pcm_vec.len();
+
+    if total_samples == 0 || chunk_size_samples == 0 {
+        return Float32Array::new_with_length(0);
+    }
+
+    // チャンクごとのdBFS値を計算 (analyze_audio_rmsと共通のチャンク分割ロジック)
+    let dbfs_values = compute_dbfs_chunks(&pcm_vec, chunk_size_samples);
+
+    // ヒステリシス付きで各チャンクを無音/有音に分類し、連続区間にまとめる
+    let states = classify_chunks_with_hysteresis(&dbfs_values, silence_threshold_dbfs);
+    let runs = coalesce_runs(&states);
+
+    // チャンク番号をサンプル番号に変換するヘルパー
+    let chunk_to_sample = |chunk_index: usize| -> usize { (chunk_index * chunk_size_samples).min(total_samples) };
+
+    // 短すぎる無音区間を有音として再分類し、隣接する区間と1回の線形走査でまとめる
+    let runs = merge_short_silences(&runs, chunk_to_sample, sample_rate, min_silence_duration_secs);
+
+    let mut result: Vec<f32> = Vec::with_capacity(runs.len() * 3);
+    for (start_chunk, end_chunk, state) in runs {
+        let start_sample = chunk_to_sample(start_chunk);
+        let end_sample = chunk_to_sample(end_chunk);
+        let is_silence = if state == ChunkState::Silent { 1.0 } else { 0.0 };
+        result.push(start_sample as f32);
+        result.push(end_sample as f32);
+        result.push(is_silence);
+    }
+
+    Float32Array::from(result.as_slice())
+}
+
+// --- マルチチャンネル（インターリーブ）入力 ---
+
+/// `analyzeAudioRmsMultichannel`の戻り値。
+/// チャンネルごとのdBFS系列に加え、全チャンネル合算のdBFS系列を持つ。
+#[wasm_bindgen]
+pub struct MultichannelAnalysisResult {
+    per_channel_dbfs: Vec<Vec<f32>>,
+    summed_dbfs: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl MultichannelAnalysisResult {
+    /// 入力されたチャンネル数
+    #[wasm_bindgen(getter, js_name = numChannels)]
+    pub fn num_channels(&self) -> usize {
+        self.per_channel_dbfs.len()
+    }
+
+    /// 指定したチャンネルのdBFS系列 (Float32Array)
+    pub fn channel(&self, index: usize) -> Float32Array {
+        match self.per_channel_dbfs.get(index) {
+            Some(values) => Float32Array::from(values.as_slice()),
+            None => Float32Array::new_with_length(0),
+        }
+    }
+
+    /// 全チャンネルを合算した（二乗平均をチャンネル間でも平均してからsqrtした）dBFS系列
+    #[wasm_bindgen(getter)]
+    pub fn summed(&self) -> Float32Array {
+        Float32Array::from(self.summed_dbfs.as_slice())
+    }
+}
+
+/**
+ * インターリーブされたマルチチャンネルPCMデータをチャンク単位で解析します。
+ *
+ * `chunk_size_samples`はチャンネルごとのフレーム数として扱われます。各チャンクを
+ * `channels`をストライドとしてデインターリーブし、チャンネルごとに独立した
+ * RMS→dBFS系列を計算します。同時に、インターリーブされたチャンク全体（全チャンネル
+ * 合算）のRMSも計算し、合算dBFS系列として返します。これにより、ステレオ波形表示と
+ * モノラルのカット点検出を1回の解析で両立できます。
+ *
+ * @param pcm_data - インターリーブされたPCMデータ (Float32Array)
+ * @param channels - チャンネル数
+ * @param chunk_size_samples - 1チャンクあたりのチャンネルごとのサンプル数（フレーム数）
+ * @returns チャンネルごと・合算のdBFS系列を持つ `MultichannelAnalysisResult`
+ */
+#[wasm_bindgen(js_name = analyzeAudioRmsMultichannel)]
+pub fn analyze_audio_rms_multichannel(
+    pcm_data: Float32Array,
+    channels: usize,
+    chunk_size_samples: usize,
+) -> MultichannelAnalysisResult {
+    let (per_channel_dbfs, summed_dbfs) = compute_multichannel_dbfs(&pcm_data.to_vec(), channels, chunk_size_samples);
+    MultichannelAnalysisResult { per_channel_dbfs, summed_dbfs }
+}
+
+/// `analyze_audio_rms_multichannel`の中身。`Float32Array`を介さないプレーンな
+/// スライス入出力にしているため、wasmランタイムなしでも単体テストできる。
+fn compute_multichannel_dbfs(
+    pcm: &[f32],
+    channels: usize,
+    chunk_size_samples: usize,
+) -> (Vec<Vec<f32>>, Vec<f32>) {
+    if channels == 0 || chunk_size_samples == 0 || pcm.is_empty() {
+        return (vec![Vec::new(); channels], Vec::new());
+    }
+
+    let total_frames = pcm.len() / channels;
+    let mut per_channel_dbfs: Vec<Vec<f32>> = vec![Vec::new(); channels];
+    let mut summed_dbfs: Vec<f32> = Vec::new();
+
+    let mut frame_start = 0;
+    while frame_start < total_frames {
+        let frame_end = (frame_start + chunk_size_samples).min(total_frames);
+        let interleaved_chunk = &pcm[frame_start * channels..frame_end * channels];
+
+        // 全チャンネル合算: インターリーブされたサンプル全体の二乗平均をそのまま使う。
+        // これは「チャンネルごとの二乗値をsqrt前に平均する」ことと等価。
+        summed_dbfs.push(rms_to_dbfs(calculate_rms(interleaved_chunk)));
+
+        // チャンネルごとに`channels`をストライドとしてデインターリーブ
+        for (channel, channel_dbfs) in per_channel_dbfs.iter_mut().enumerate() {
+            let channel_samples: Vec<f32> =
+                interleaved_chunk.iter().skip(channel).step_by(channels).copied().collect();
+            channel_dbfs.push(rms_to_dbfs(calculate_rms(&channel_samples)));
+        }
+
+        frame_start = frame_end;
+    }
+
+    (per_channel_dbfs, summed_dbfs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `leave_silence_dbfs`付近で値が上下しても、ヒステリシス幅を超えない限り
+    /// 無音から抜けた判定にフリッカーしないことを確認する。
+    #[test]
+    fn hysteresis_prevents_flicker_near_threshold() {
+        let leave_silence_dbfs = -40.0;
+        // -40dBFSをわずかに上回る値が続くだけでは、ヒステリシス幅(3dB)に
+        // 満たないため無音状態を維持するはず
+        let dbfs_values = [-50.0, -39.5, -39.8, -39.2, -50.0];
+
+        let states = classify_chunks_with_hysteresis(&dbfs_values, leave_silence_dbfs);
+
+        assert_eq!(
+            states,
+            vec![
+                ChunkState::Silent,
+                ChunkState::Silent,
+                ChunkState::Silent,
+                ChunkState::Silent,
+                ChunkState::Silent,
+            ]
+        );
+    }
+
+    #[test]
+    fn hysteresis_leaves_silence_once_margin_is_exceeded() {
+        let leave_silence_dbfs = -40.0;
+        // -40 + 3 (SILENCE_HYSTERESIS_DB) を超えたら有音に戻る
+        let dbfs_values = [-50.0, -30.0, -50.0];
+
+        let states = classify_chunks_with_hysteresis(&dbfs_values, leave_silence_dbfs);
+
+        assert_eq!(states, vec![ChunkState::Silent, ChunkState::Active, ChunkState::Silent]);
+    }
+
+    #[test]
+    fn coalesce_runs_groups_consecutive_equal_states() {
+        let states = [
+            ChunkState::Active,
+            ChunkState::Active,
+            ChunkState::Silent,
+            ChunkState::Silent,
+            ChunkState::Silent,
+            ChunkState::Active,
+        ];
+
+        let runs = coalesce_runs(&states);
+
+        assert_eq!(
+            runs,
+            vec![(0, 2, ChunkState::Active), (2, 5, ChunkState::Silent), (5, 6, ChunkState::Active)]
+        );
+    }
+
+    /// 単語間の小さな息継ぎのような、短すぎる無音区間は有音として
+    /// 前後の有音区間と結合され、1つの区間にまとまることを確認する。
+    #[test]
+    fn merge_short_silences_swallows_brief_gaps_between_active_runs() {
+        // チャンクサイズ1サンプル、サンプルレート10Hzとすると、1チャンク=0.1秒
+        let chunk_size_samples = 1;
+        let sample_rate = 10.0;
+        let min_silence_duration_secs = 0.5;
+        let chunk_to_sample = |chunk_index: usize| chunk_index * chunk_size_samples;
+
+        // 有音(3チャンク=0.3s) - 無音(2チャンク=0.2s, 短い) - 有音(3チャンク=0.3s)
+        let runs = vec![
+            (0usize, 3usize, ChunkState::Active),
+            (3, 5, ChunkState::Silent),
+            (5, 8, ChunkState::Active),
+        ];
+
+        let merged = merge_short_silences(&runs, chunk_to_sample, sample_rate, min_silence_duration_secs);
+
+        assert_eq!(merged, vec![(0, 8, ChunkState::Active)]);
+    }
+
+    /// 十分に長い無音区間は有音として再分類されず、そのまま無音区間として残る。
+    #[test]
+    fn merge_short_silences_keeps_long_enough_gaps() {
+        let chunk_size_samples = 1;
+        let sample_rate = 10.0;
+        let min_silence_duration_secs = 0.5;
+        let chunk_to_sample = |chunk_index: usize| chunk_index * chunk_size_samples;
+
+        // 無音区間は6チャンク=0.6sでしきい値(0.5s)以上なので無音のまま
+        let runs = vec![
+            (0usize, 3usize, ChunkState::Active),
+            (3, 9, ChunkState::Silent),
+            (9, 12, ChunkState::Active),
+        ];
+
+        let merged = merge_short_silences(&runs, chunk_to_sample, sample_rate, min_silence_duration_secs);
+
+        assert_eq!(
+            merged,
+            vec![(0, 3, ChunkState::Active), (3, 9, ChunkState::Silent), (9, 12, ChunkState::Active)]
+        );
+    }
+
+    /// インターリーブされたステレオ信号を既知の値でデインターリーブし、
+    /// チャンネルごとのRMS(→dBFS)が独立に正しく計算されることを確認する。
+    #[test]
+    fn deinterleaves_stereo_signal_per_channel() {
+        // L: 1.0, 1.0 / R: 0.0, 0.0 の2フレーム。チャンクサイズ=2フレームで1チャンクにまとめる。
+        let pcm = [1.0, 0.0, 1.0, 0.0];
+        let (per_channel_dbfs, _) = compute_multichannel_dbfs(&pcm, 2, 2);
+
+        assert_eq!(per_channel_dbfs.len(), 2);
+        assert_eq!(per_channel_dbfs[0].len(), 1);
+        assert_eq!(per_channel_dbfs[1].len(), 1);
+        // Lチャンネルは振幅1.0の一定信号なのでdBFS=0、Rチャンネルは無音なので非常に低いdBFS
+        assert!((per_channel_dbfs[0][0] - 0.0).abs() < 1.0e-4);
+        assert!(per_channel_dbfs[1][0] < -100.0);
+    }
+
+    /// `summed`チャンネルの値が、インターリーブされたチャンク全体に対する
+    /// 独立計算のRMS(→dBFS)と一致することを確認する。
+    #[test]
+    fn summed_channel_matches_independently_computed_combined_rms() {
+        let pcm = [1.0, 0.0, -1.0, 0.5, 0.5, -0.5];
+        let (_, summed_dbfs) = compute_multichannel_dbfs(&pcm, 2, 3);
+
+        let expected = rms_to_dbfs(calculate_rms(&pcm));
+        assert_eq!(summed_dbfs.len(), 1);
+        assert!((summed_dbfs[0] - expected).abs() < 1.0e-6);
+    }
+
+    /// チャンネル数0は各種スライスが空の結果を返す（ゼロ除算を避ける）。
+    #[test]
+    fn zero_channels_returns_empty_result() {
+        let (per_channel_dbfs, summed_dbfs) = compute_multichannel_dbfs(&[1.0, 2.0, 3.0], 0, 1);
+
+        assert!(per_channel_dbfs.is_empty());
+        assert!(summed_dbfs.is_empty());
+    }
+
+    /// サンプル数がチャンネル数の倍数でない場合、末尾の不完全なフレームは
+    /// 単純な整数除算で切り捨てられ、パニックしないことを確認する。
+    #[test]
+    fn non_multiple_of_channels_length_truncates_incomplete_trailing_frame() {
+        // 2チャンネルに対してサンプル5個（2.5フレーム分）。末尾の1サンプルは無視される。
+        let pcm = [1.0, 1.0, 1.0, 1.0, 1.0];
+        let (per_channel_dbfs, summed_dbfs) = compute_multichannel_dbfs(&pcm, 2, 10);
+
+        // total_frames = 5 / 2 = 2 フレームのみ処理される
+        assert_eq!(per_channel_dbfs[0].len(), 1);
+        assert_eq!(summed_dbfs.len(), 1);
+        assert!((per_channel_dbfs[0][0] - 0.0).abs() < 1.0e-4);
+        assert!((per_channel_dbfs[1][0] - 0.0).abs() < 1.0e-4);
+    }
+
+    /// フレーム単位のチャンキングが、サンプル単位ではなくチャンネルごとの
+    /// フレーム数で区切られることを確認する（1チャンク=frames×channelsサンプル）。
+    #[test]
+    fn chunk_size_is_interpreted_as_frames_not_raw_samples() {
+        // 3チャンネル、チャンクサイズ=1フレームなら、1チャンクは3サンプル分になるはず
+        let pcm = [1.0, 1.0, 1.0, 0.0, 0.0, 0.0];
+        let (per_channel_dbfs, summed_dbfs) = compute_multichannel_dbfs(&pcm, 3, 1);
+
+        assert_eq!(summed_dbfs.len(), 2);
+        for channel_dbfs in &per_channel_dbfs {
+            assert_eq!(channel_dbfs.len(), 2);
+        }
+    }
 }
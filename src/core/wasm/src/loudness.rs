@@ -0,0 +1,294 @@
+//! EBU R128 (ITU-R BS.1770) 準拠のラウドネス計測。
+//!
+//! 生のRMS→dBFSは録音レベルの目安にはなるが、人間の知覚音量とはズレがある。
+//! ここではK特性フィルタ（高域シェルフ＋高域通過の2段ビクアッド）を適用した上で、
+//! 400msブロック・75%オーバーラップでの短時間ラウドネスと、
+//! 2段階ゲーティングによる統合ラウドネスを計算する。
+
+use wasm_bindgen::prelude::*;
+use js_sys::Float32Array;
+
+/// 無音ブロックでの対数計算エラー（-Infinity）を防ぐための最小エネルギー値
+const MIN_MEAN_SQUARE: f64 = 1.0e-12;
+
+/// ITU-R BS.1770 絶対ゲート (LUFS)
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+/// 相対ゲートのオフセット (LU)
+const RELATIVE_GATE_OFFSET_LU: f64 = 10.0;
+
+/// ブロック長 (秒)
+const BLOCK_DURATION_SECS: f64 = 0.4;
+/// ホップ長 (秒)。75%オーバーラップ = 25%ホップ
+const HOP_DURATION_SECS: f64 = 0.1;
+
+/// Direct Form I のビクアッドフィルタ係数（`a0`で正規化済み）
+#[derive(Clone, Copy)]
+struct BiquadCoeffs {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+/// ビクアッドフィルタの内部状態（直前2サンプル分の入出力）
+#[derive(Default)]
+struct BiquadState {
+    x1: f64,
+    x2: f64,
+    y1: f64,
+    y2: f64,
+}
+
+impl BiquadState {
+    fn process(&mut self, coeffs: &BiquadCoeffs, x0: f64) -> f64 {
+        let y0 = coeffs.b0 * x0 + coeffs.b1 * self.x1 + coeffs.b2 * self.x2
+            - coeffs.a1 * self.y1
+            - coeffs.a2 * self.y2;
+
+        self.x2 = self.x1;
+        self.x1 = x0;
+        self.y2 = self.y1;
+        self.y1 = y0;
+
+        y0
+    }
+}
+
+/// RBJ Audio EQ Cookbookの高域シェルフ（high-shelf）係数。
+/// K特性の最初の段: ~1.5kHz以上を`gain_db`だけ持ち上げる。
+fn high_shelf_coeffs(sample_rate: f64, center_freq: f64, q: f64, gain_db: f64) -> BiquadCoeffs {
+    let a = 10f64.powf(gain_db / 40.0);
+    let w0 = 2.0 * std::f64::consts::PI * center_freq / sample_rate;
+    let cos_w0 = w0.cos();
+    let alpha = w0.sin() / (2.0 * q);
+    let two_sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+    let b0 = a * ((a + 1.0) + (a - 1.0) * cos_w0 + two_sqrt_a_alpha);
+    let b1 = -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0);
+    let b2 = a * ((a + 1.0) + (a - 1.0) * cos_w0 - two_sqrt_a_alpha);
+    let a0 = (a + 1.0) - (a - 1.0) * cos_w0 + two_sqrt_a_alpha;
+    let a1 = 2.0 * ((a - 1.0) - (a + 1.0) * cos_w0);
+    let a2 = (a + 1.0) - (a - 1.0) * cos_w0 - two_sqrt_a_alpha;
+
+    BiquadCoeffs { b0: b0 / a0, b1: b1 / a0, b2: b2 / a0, a1: a1 / a0, a2: a2 / a0 }
+}
+
+/// RBJ Audio EQ Cookbookの高域通過（high-pass）係数。
+/// K特性の2段目 "RLB" フィルタ: ~38Hz以下を減衰させる。
+fn high_pass_coeffs(sample_rate: f64, cutoff_freq: f64, q: f64) -> BiquadCoeffs {
+    let w0 = 2.0 * std::f64::consts::PI * cutoff_freq / sample_rate;
+    let cos_w0 = w0.cos();
+    let alpha = w0.sin() / (2.0 * q);
+
+    let b0 = (1.0 + cos_w0) / 2.0;
+    let b1 = -(1.0 + cos_w0);
+    let b2 = (1.0 + cos_w0) / 2.0;
+    let a0 = 1.0 + alpha;
+    let a1 = -2.0 * cos_w0;
+    let a2 = 1.0 - alpha;
+
+    BiquadCoeffs { b0: b0 / a0, b1: b1 / a0, b2: b2 / a0, a1: a1 / a0, a2: a2 / a0 }
+}
+
+/// 2段ビクアッド（高域シェルフ→RLBハイパス）によるK特性フィルタを信号全体に適用する。
+fn apply_k_weighting(samples: &[f32], sample_rate: f64) -> Vec<f64> {
+    let shelf_coeffs = high_shelf_coeffs(sample_rate, 1500.0, std::f64::consts::FRAC_1_SQRT_2, 4.0);
+    let rlb_coeffs = high_pass_coeffs(sample_rate, 38.0, 0.5);
+
+    let mut shelf_state = BiquadState::default();
+    let mut rlb_state = BiquadState::default();
+
+    samples
+        .iter()
+        .map(|&sample| {
+            let shelved = shelf_state.process(&shelf_coeffs, sample as f64);
+            rlb_state.process(&rlb_coeffs, shelved)
+        })
+        .collect()
+}
+
+/// K特性フィルタ適用後の信号を400msブロック・75%オーバーラップで走査し、
+/// 各ブロックの平均二乗エネルギーを返す。
+fn block_mean_squares(weighted: &[f64], sample_rate: f64) -> Vec<f64> {
+    let block_len = (BLOCK_DURATION_SECS * sample_rate).round() as usize;
+    let hop_len = (HOP_DURATION_SECS * sample_rate).round() as usize;
+
+    if block_len == 0 || hop_len == 0 || weighted.len() < block_len {
+        return Vec::new();
+    }
+
+    let mut mean_squares = Vec::new();
+    let mut start = 0;
+    while start + block_len <= weighted.len() {
+        let block = &weighted[start..start + block_len];
+        let sum_sq: f64 = block.iter().map(|&s| s * s).sum();
+        mean_squares.push(sum_sq / block_len as f64);
+        start += hop_len;
+    }
+
+    mean_squares
+}
+
+fn mean_square_to_loudness(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.max(MIN_MEAN_SQUARE).log10()
+}
+
+/// ITU-R BS.1770の2段階ゲーティングによる統合ラウドネスを計算する。
+fn gated_integrated_loudness(block_mean_squares: &[f64]) -> f64 {
+    if block_mean_squares.is_empty() {
+        return mean_square_to_loudness(0.0);
+    }
+
+    // 第1段: 絶対ゲート (-70 LUFS以下のブロックを除外)
+    let above_absolute_gate: Vec<f64> = block_mean_squares
+        .iter()
+        .copied()
+        .filter(|&ms| mean_square_to_loudness(ms) > ABSOLUTE_GATE_LUFS)
+        .collect();
+
+    if above_absolute_gate.is_empty() {
+        return mean_square_to_loudness(0.0);
+    }
+
+    // 第2段: 絶対ゲートを通過したブロックの平均から相対ゲートを求める
+    let ungated_mean = above_absolute_gate.iter().sum::<f64>() / above_absolute_gate.len() as f64;
+    let relative_gate = mean_square_to_loudness(ungated_mean) - RELATIVE_GATE_OFFSET_LU;
+
+    let above_relative_gate: Vec<f64> = above_absolute_gate
+        .iter()
+        .copied()
+        .filter(|&ms| mean_square_to_loudness(ms) > relative_gate)
+        .collect();
+
+    if above_relative_gate.is_empty() {
+        return mean_square_to_loudness(ungated_mean);
+    }
+
+    let gated_mean = above_relative_gate.iter().sum::<f64>() / above_relative_gate.len() as f64;
+    mean_square_to_loudness(gated_mean)
+}
+
+/// `analyzeLoudnessLufs`の戻り値。短時間ラウドネスの系列と統合ラウドネスを両方持つ。
+#[wasm_bindgen]
+pub struct LoudnessResult {
+    short_term_lufs: Vec<f32>,
+    integrated_lufs: f32,
+}
+
+#[wasm_bindgen]
+impl LoudnessResult {
+    /// 400msブロック・75%オーバーラップごとの短時間ラウドネス (LUFS)
+    #[wasm_bindgen(getter, js_name = shortTerm)]
+    pub fn short_term(&self) -> Float32Array {
+        Float32Array::from(self.short_term_lufs.as_slice())
+    }
+
+    /// 2段階ゲーティングを適用した統合ラウドネス (LUFS)
+    #[wasm_bindgen(getter)]
+    pub fn integrated(&self) -> f32 {
+        self.integrated_lufs
+    }
+}
+
+/**
+ * EBU R128 (ITU-R BS.1770) に基づく短時間ラウドネスと統合ラウドネスを計算します。
+ *
+ * K特性フィルタ（~1.5kHz以上を+4dB持ち上げる高域シェルフ → ~38HzのRLBハイパス）を
+ * 信号全体に適用した後、400msブロック・75%オーバーラップ（100msホップ）で
+ * 平均二乗エネルギーを求め、`-0.691 + 10*log10(meanSquare)`でブロックごとの
+ * 短時間ラウドネスに変換します。
+ *
+ * 統合ラウドネスは2段階ゲーティングで求めます: まず-70 LUFS以下の絶対ゲートで
+ * 無音に近いブロックを除外し、残ったブロックの平均から「ゲート後平均 - 10 LU」を
+ * 相対ゲートとして算出、それを上回るブロックのエネルギーのみを平均して
+ * 最終的なラウドネスとします。
+ *
+ * @param pcm_data - 音声データ (Float32Array、モノラル想定)
+ * @param sample_rate - サンプリングレート (Hz)
+ * @returns 短時間ラウドネスの系列と統合ラウドネスを持つ `LoudnessResult`
+ */
+#[wasm_bindgen(js_name = analyzeLoudnessLufs)]
+pub fn analyze_loudness_lufs(pcm_data: Float32Array, sample_rate: f32) -> LoudnessResult {
+    let samples: Vec<f32> = pcm_data.to_vec();
+    let sample_rate = sample_rate as f64;
+
+    let weighted = apply_k_weighting(&samples, sample_rate);
+    let mean_squares = block_mean_squares(&weighted, sample_rate);
+
+    let short_term_lufs: Vec<f32> = mean_squares.iter().map(|&ms| mean_square_to_loudness(ms) as f32).collect();
+    let integrated_lufs = gated_integrated_loudness(&mean_squares) as f32;
+
+    LoudnessResult { short_term_lufs, integrated_lufs }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_square_to_loudness_matches_known_formula() {
+        // meanSquare = 1.0 (0 dBFS相当) なら log10(1) = 0 なので -0.691 LUFSちょうど
+        assert!((mean_square_to_loudness(1.0) - (-0.691)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn block_mean_squares_of_constant_signal_matches_amplitude_squared() {
+        let sample_rate = 1000.0;
+        // 400msブロック = 400サンプル @1000Hz。十分な長さの一定振幅信号を用意する
+        let amplitude = 0.5_f64;
+        let weighted = vec![amplitude; 2000];
+
+        let blocks = block_mean_squares(&weighted, sample_rate);
+
+        assert!(!blocks.is_empty());
+        for mean_square in blocks {
+            assert!((mean_square - amplitude * amplitude).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn block_mean_squares_empty_when_shorter_than_one_block() {
+        let sample_rate = 1000.0;
+        let weighted = vec![1.0; 100]; // 400msブロックに満たない
+
+        assert!(block_mean_squares(&weighted, sample_rate).is_empty());
+    }
+
+    #[test]
+    fn digital_silence_bottoms_out_at_the_min_mean_square_floor() {
+        let sample_rate = 48_000.0;
+        let samples = vec![0.0_f32; sample_rate as usize * 2];
+
+        let result = analyze_loudness_lufs_for_test(&samples, sample_rate as f32);
+
+        // 無音はMIN_MEAN_SQUAREでクランプされた非常に低い値になる
+        assert!(result < -100.0);
+    }
+
+    #[test]
+    fn louder_sine_has_higher_integrated_loudness_than_quieter_sine() {
+        let sample_rate = 48_000.0_f32;
+        let quiet = sine_wave(440.0, 0.1, sample_rate, 2.0);
+        let loud = sine_wave(440.0, 0.8, sample_rate, 2.0);
+
+        let quiet_lufs = analyze_loudness_lufs_for_test(&quiet, sample_rate);
+        let loud_lufs = analyze_loudness_lufs_for_test(&loud, sample_rate);
+
+        assert!(loud_lufs > quiet_lufs);
+    }
+
+    /// WASM境界を挟まずに統合ラウドネスだけを取り出すテスト用ヘルパー
+    fn analyze_loudness_lufs_for_test(samples: &[f32], sample_rate: f32) -> f64 {
+        let weighted = apply_k_weighting(samples, sample_rate as f64);
+        let mean_squares = block_mean_squares(&weighted, sample_rate as f64);
+        gated_integrated_loudness(&mean_squares)
+    }
+
+    fn sine_wave(freq_hz: f32, amplitude: f32, sample_rate: f32, duration_secs: f32) -> Vec<f32> {
+        let num_samples = (sample_rate * duration_secs) as usize;
+        (0..num_samples)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq_hz * i as f32 / sample_rate).sin())
+            .collect()
+    }
+}
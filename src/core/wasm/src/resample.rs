@@ -0,0 +1,175 @@
+//! 帯域制限付きリサンプリング（ウィンドウ付きsinc FIR）。
+//!
+//! サンプルを単純に間引くとエイリアシングが発生するため、
+//! 先にローパスFIRで帯域制限してからリサンプリングする。
+//! カットオフは `min(from_rate, to_rate) / 2` とし、sinc関数をBlackman窓で
+//! 有限長に切り詰めたカーネルを各出力位置ごとに畳み込む。
+
+use wasm_bindgen::prelude::*;
+use js_sys::Float32Array;
+
+use crate::compute_dbfs_chunks;
+
+/// Blackman窓。`position`は`0.0..=1.0`（窓の左端〜右端）を表す。
+fn blackman_window(position: f64) -> f64 {
+    const A0: f64 = 0.42;
+    const A1: f64 = 0.5;
+    const A2: f64 = 0.08;
+
+    let two_pi_pos = 2.0 * std::f64::consts::PI * position;
+    let four_pi_pos = 2.0 * two_pi_pos;
+    A0 - A1 * two_pi_pos.cos() + A2 * four_pi_pos.cos()
+}
+
+/// 正規化カットオフ`fc`（サンプリングレートに対する比率）のローパスFIRの
+/// インパルス応答を、入力サンプル単位のオフセット`offset`で評価する。
+fn sinc_lowpass(offset: f64, fc: f64) -> f64 {
+    if offset.abs() < 1.0e-9 {
+        2.0 * fc
+    } else {
+        let x = 2.0 * std::f64::consts::PI * fc * offset;
+        x.sin() / (std::f64::consts::PI * offset)
+    }
+}
+
+/**
+ * ウィンドウ付きsinc（ポリフェーズFIR）によるアンチエイリアシング・リサンプリング。
+ *
+ * カットオフ `min(from_rate, to_rate) / 2` のsincローパスカーネルをBlackman窓で
+ * 有限長（`filter_half_width`できまる長さ）に切り詰め、出力位置ごとに対応する
+ * 入力上の小数位置を求めて近傍の入力サンプルと畳み込む。整数比の間引きの場合も
+ * 特別扱いはせず、同じ畳み込みが各出力タップに対して実行される（結果的に固定の
+ * 間引きになる）。
+ *
+ * @param input - 入力サンプル列
+ * @param from_rate - 入力のサンプリングレート (Hz)
+ * @param to_rate - 出力のサンプリングレート (Hz)
+ * @param filter_half_width - カーネルの片側タップ数（大きいほど高品質・低速）
+ * @returns リサンプリング後のサンプル列
+ */
+fn resample_windowed_sinc(input: &[f32], from_rate: f64, to_rate: f64, filter_half_width: usize) -> Vec<f32> {
+    if input.is_empty() || from_rate <= 0.0 || to_rate <= 0.0 || filter_half_width == 0 {
+        return Vec::new();
+    }
+
+    let cutoff_hz = from_rate.min(to_rate) / 2.0;
+    let fc = cutoff_hz / from_rate;
+    let ratio = from_rate / to_rate;
+
+    let output_len = ((input.len() as f64) * (to_rate / from_rate)).round() as usize;
+    let mut output = Vec::with_capacity(output_len);
+
+    let half_width = filter_half_width as isize;
+    let window_width = (2 * filter_half_width) as f64;
+
+    for n in 0..output_len {
+        // 出力サンプルnに対応する、入力サンプル単位での（小数を含む）位置
+        let x = n as f64 * ratio;
+        let center = x.floor() as isize;
+
+        let mut acc = 0.0f64;
+        for j in -half_width + 1..=half_width {
+            let k = center + j;
+            if k < 0 || k as usize >= input.len() {
+                continue;
+            }
+
+            let offset = x - k as f64;
+            let window_pos = ((offset + half_width as f64) / window_width).clamp(0.0, 1.0);
+
+            acc += input[k as usize] as f64 * sinc_lowpass(offset, fc) * blackman_window(window_pos);
+        }
+
+        output.push(acc as f32);
+    }
+
+    output
+}
+
+/**
+ * 入力を帯域制限しながらリサンプリングした上で、既存のチャンク解析
+ * （RMS→dBFS）を実行します。48kHzの音声をUI表示用の粗い envelope に
+ * 落とし込むようなケースで、単純な間引きによるエイリアシングを避けつつ
+ * 解析対象のサンプル数を減らすために使います。
+ *
+ * @param pcm_data - 入力サンプル列
+ * @param from_rate - 入力のサンプリングレート (Hz)
+ * @param to_rate - リサンプリング後のサンプリングレート (Hz)
+ * @param chunk_size_samples - リサンプリング後の信号に対する1チャンクあたりのサンプル数
+ * @param filter_half_width - リサンプリングFIRカーネルの片側タップ数（品質パラメータ）
+ * @returns 各チャンクのdBFS値が格納された Float32Array
+ */
+#[wasm_bindgen(js_name = resampleThenAnalyze)]
+pub fn resample_then_analyze(
+    pcm_data: Float32Array,
+    from_rate: f32,
+    to_rate: f32,
+    chunk_size_samples: usize,
+    filter_half_width: usize,
+) -> Float32Array {
+    let input: Vec<f32> = pcm_data.to_vec();
+    let resampled = resample_windowed_sinc(&input, from_rate as f64, to_rate as f64, filter_half_width);
+
+    Float32Array::from(compute_dbfs_chunks(&resampled, chunk_size_samples).as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine_wave(freq_hz: f64, sample_rate: f64, num_samples: usize) -> Vec<f32> {
+        (0..num_samples)
+            .map(|i| (2.0 * std::f64::consts::PI * freq_hz * i as f64 / sample_rate).sin() as f32)
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f64 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        let sum_sq: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+        (sum_sq / samples.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn ratio_one_resample_preserves_amplitude() {
+        let sample_rate = 8000.0;
+        let input = sine_wave(200.0, sample_rate, 4000);
+
+        let output = resample_windowed_sinc(&input, sample_rate, sample_rate, 16);
+
+        assert_eq!(output.len(), input.len());
+        // 端のタップ不足区間を避けるため、中央部分のRMSで比較する
+        let interior = &output[500..3500];
+        let input_interior = &input[500..3500];
+        assert!((rms(interior) - rms(input_interior)).abs() < 0.05);
+    }
+
+    #[test]
+    fn downsample_attenuates_content_above_new_nyquist() {
+        let from_rate = 8000.0;
+        let to_rate = 2000.0; // 新しいナイキスト周波数は1000Hz
+        let num_samples = 4000;
+
+        // 通過域 (200Hz、新ナイキスト未満) は振幅が保たれるはず
+        let passband_input = sine_wave(200.0, from_rate, num_samples);
+        let passband_output = resample_windowed_sinc(&passband_input, from_rate, to_rate, 16);
+
+        // 阻止域 (1800Hz、新ナイキスト超) はエイリアシング防止のため大きく減衰するはず
+        let stopband_input = sine_wave(1800.0, from_rate, num_samples);
+        let stopband_output = resample_windowed_sinc(&stopband_input, from_rate, to_rate, 16);
+
+        let passband_rms = rms(&passband_output[50..passband_output.len() - 50]);
+        let stopband_rms = rms(&stopband_output[50..stopband_output.len() - 50]);
+
+        // 元の正弦波のRMSはどちらも概ね1/sqrt(2)だが、阻止域側だけが大きく減衰しているはず
+        assert!(passband_rms > 0.5);
+        assert!(stopband_rms < passband_rms * 0.3);
+    }
+
+    #[test]
+    fn empty_input_or_zero_quality_returns_empty_output() {
+        assert!(resample_windowed_sinc(&[], 8000.0, 2000.0, 16).is_empty());
+        assert!(resample_windowed_sinc(&[1.0, 2.0, 3.0], 8000.0, 2000.0, 0).is_empty());
+    }
+}
@@ -0,0 +1,409 @@
+//! RIFF/WAVEコンテナのパースとPCMデコード。
+//!
+//! JS側で`AudioContext.decodeAudioData`等を使わずに、WAVバイト列から直接
+//! 正規化済みのPCMサンプルを取り出し、既存のチャンク解析にそのまま渡せるようにする。
+
+use wasm_bindgen::prelude::*;
+
+use crate::compute_dbfs_chunks;
+
+/// `fmt `チャンクのオーディオフォーマットコード: リニアPCM
+const WAVE_FORMAT_PCM: u16 = 1;
+/// `fmt `チャンクのオーディオフォーマットコード: IEEE 754 浮動小数点PCM
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// `fmt `チャンクから読み取ったフォーマット情報
+#[derive(Debug)]
+struct WavFormat {
+    audio_format: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+/// `analyzeWavRms`の戻り値。dBFS値の系列に加えて、チャンク番号を秒数に
+/// 変換するために必要なサンプルレートとチャンネル数を一緒に返す。
+#[wasm_bindgen]
+pub struct WavAnalysisResult {
+    dbfs_values: Vec<f32>,
+    sample_rate: u32,
+    channels: u16,
+}
+
+#[wasm_bindgen]
+impl WavAnalysisResult {
+    /// 各チャンクのdBFS値 (Float32Array)
+    #[wasm_bindgen(getter)]
+    pub fn dbfs(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(self.dbfs_values.as_slice())
+    }
+
+    /// WAVヘッダから読み取ったサンプリングレート (Hz)
+    #[wasm_bindgen(getter, js_name = sampleRate)]
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// WAVヘッダから読み取ったチャンネル数
+    #[wasm_bindgen(getter)]
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+}
+
+/// WAVパース時に起こりうるエラー。`JsValue`を持たせずプレーンなRustの型として
+/// 持ち回ることで、wasmランタイムなしの単体テストでもエラー経路を直接検証できる
+/// ようにしている（`JsValue`への変換は`wasm_bindgen`関数の境界でのみ行う）。
+#[derive(Debug, PartialEq, Eq)]
+enum WavParseError {
+    MissingTag { offset: usize, tag: &'static str },
+    Truncated,
+    ChunkOutOfBounds,
+    MissingFmtChunk,
+    MissingDataChunk,
+    UnsupportedFormat { format_code: u16, bits_per_sample: u16 },
+}
+
+impl From<WavParseError> for JsValue {
+    fn from(err: WavParseError) -> JsValue {
+        JsValue::from_str(&match err {
+            WavParseError::MissingTag { offset, tag } => {
+                format!("WAVヘッダが不正です: オフセット{offset}に\"{tag}\"が見つかりません")
+            }
+            WavParseError::Truncated => "WAVデータが途中で終了しています".to_string(),
+            WavParseError::ChunkOutOfBounds => "WAVチャンクサイズがデータ範囲を超えています".to_string(),
+            WavParseError::MissingFmtChunk => "WAVに\"fmt \"チャンクが見つかりません".to_string(),
+            WavParseError::MissingDataChunk => "WAVに\"data\"チャンクが見つかりません".to_string(),
+            WavParseError::UnsupportedFormat { format_code, bits_per_sample } => format!(
+                "未対応のWAVフォーマットです (format={format_code}, bits_per_sample={bits_per_sample})"
+            ),
+        })
+    }
+}
+
+/// `bytes[offset..offset+4]`が期待するASCIIタグと一致するかを確認する。
+fn expect_tag(bytes: &[u8], offset: usize, tag: &'static [u8; 4]) -> Result<(), WavParseError> {
+    if bytes.len() < offset + 4 || &bytes[offset..offset + 4] != tag {
+        return Err(WavParseError::MissingTag {
+            offset,
+            tag: std::str::from_utf8(tag).unwrap_or("?"),
+        });
+    }
+    Ok(())
+}
+
+fn read_u32_le(bytes: &[u8], offset: usize) -> Result<u32, WavParseError> {
+    let slice = bytes.get(offset..offset + 4).ok_or(WavParseError::Truncated)?;
+    Ok(u32::from_le_bytes(slice.try_into().unwrap()))
+}
+
+fn read_u16_le(bytes: &[u8], offset: usize) -> Result<u16, WavParseError> {
+    let slice = bytes.get(offset..offset + 2).ok_or(WavParseError::Truncated)?;
+    Ok(u16::from_le_bytes(slice.try_into().unwrap()))
+}
+
+/// チャンク本体の終端オフセットを、桁あふれと実際のバイト列の範囲の両方を
+/// 検証した上で計算する。`chunk_size`はファイルから読み取った値なので、
+/// `body_start + chunk_size`が`usize`の範囲を超えたり実際のバイト列の範囲を
+/// 超えたりする可能性がある（`0xFFFFFFFF`は「長さ不明」を表す慣習として実在し、
+/// 検証なしにスライスするとwasm32（32bit usize）で桁あふれしパニックする）。
+/// どちらかの条件を満たさない場合は`None`を返す。
+fn chunk_body_end(body_start: usize, chunk_size: usize, total_len: usize) -> Option<usize> {
+    body_start.checked_add(chunk_size).filter(|&end| end <= total_len)
+}
+
+/// `RIFF`/`WAVE`ヘッダに続くチャンクを走査し、`fmt `と`data`チャンクを見つける。
+/// `LIST`や`JUNK`など未知のチャンクはサイズ分だけスキップする。
+fn parse_chunks(bytes: &[u8]) -> Result<(WavFormat, &[u8]), WavParseError> {
+    expect_tag(bytes, 0, b"RIFF")?;
+    expect_tag(bytes, 8, b"WAVE")?;
+
+    let mut format: Option<WavFormat> = None;
+    let mut data: Option<&[u8]> = None;
+
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let chunk_id = &bytes[offset..offset + 4];
+        let chunk_size = read_u32_le(bytes, offset + 4)? as usize;
+        let body_start = offset + 8;
+        let body_end =
+            chunk_body_end(body_start, chunk_size, bytes.len()).ok_or(WavParseError::ChunkOutOfBounds)?;
+        let body = &bytes[body_start..body_end];
+
+        match chunk_id {
+            b"fmt " => {
+                format = Some(WavFormat {
+                    audio_format: read_u16_le(body, 0)?,
+                    channels: read_u16_le(body, 2)?,
+                    sample_rate: read_u32_le(body, 4)?,
+                    bits_per_sample: read_u16_le(body, 14)?,
+                });
+            }
+            b"data" => {
+                data = Some(body);
+            }
+            // `LIST`、`JUNK`などの未知/不要なチャンクは読み飛ばす
+            _ => {}
+        }
+
+        // チャンクサイズが奇数の場合、ワード境界に揃えるためのパディングバイトが1つ続く
+        let padding = chunk_size % 2;
+        offset = body_end + padding;
+    }
+
+    let format = format.ok_or(WavParseError::MissingFmtChunk)?;
+    let data = data.ok_or(WavParseError::MissingDataChunk)?;
+
+    Ok((format, data))
+}
+
+/// `data`チャンクの生バイト列を、フォーマットに応じて`-1.0..=1.0`に正規化した`f32`に変換する。
+fn decode_samples(format: &WavFormat, data: &[u8]) -> Result<Vec<f32>, WavParseError> {
+    match (format.audio_format, format.bits_per_sample) {
+        (WAVE_FORMAT_PCM, 8) => {
+            // 8bit PCMは符号なし、中心値128がゼロ
+            Ok(data.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect())
+        }
+        (WAVE_FORMAT_PCM, 16) => Ok(data
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+            .collect()),
+        (WAVE_FORMAT_PCM, 24) => Ok(data
+            .chunks_exact(3)
+            .map(|b| {
+                // 24bit符号付き整数をi32に符号拡張してから正規化する
+                let raw = i32::from_le_bytes([b[0], b[1], b[2], if b[2] & 0x80 != 0 { 0xFF } else { 0x00 }]);
+                raw as f32 / 8_388_608.0
+            })
+            .collect()),
+        (WAVE_FORMAT_PCM, 32) => Ok(data
+            .chunks_exact(4)
+            .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0)
+            .collect()),
+        (WAVE_FORMAT_IEEE_FLOAT, 32) => Ok(data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()),
+        (format_code, bits_per_sample) => Err(WavParseError::UnsupportedFormat { format_code, bits_per_sample }),
+    }
+}
+
+/**
+ * WAVファイルのバイト列を受け取り、RIFF/WAVEコンテナをパースしてPCMをデコードし、
+ * 既存のチャンク解析ロジックでdBFS値の系列を計算します。
+ *
+ * `RIFF`/`WAVE`ヘッダを検証した後、バイト列の末尾までチャンクを走査して
+ * `fmt `チャンクと`data`チャンクを探します（`LIST`/`JUNK`など未知のチャンクは読み飛ばします）。
+ * 8/16/24/32bit整数PCMおよび32bit浮動小数点PCMを`-1.0..=1.0`の`f32`に変換してから
+ * `analyzeAudioRms`と同じチャンク分割・RMS計算を行います。
+ *
+ * @param bytes - WAVファイルの生バイト列
+ * @param chunk_size_samples - 1チャンクあたりのサンプル数（全チャンネル合算後のフレーム数ではなく、サンプル列に対する数）
+ * @returns dBFS値の系列とサンプリングレート/チャンネル数を持つ `WavAnalysisResult`
+ */
+#[wasm_bindgen(js_name = analyzeWavRms)]
+pub fn analyze_wav_rms(bytes: &[u8], chunk_size_samples: usize) -> Result<WavAnalysisResult, JsValue> {
+    let (format, data) = parse_chunks(bytes)?;
+    let samples = decode_samples(&format, data)?;
+    let dbfs_values = compute_dbfs_chunks(&samples, chunk_size_samples);
+
+    Ok(WavAnalysisResult {
+        dbfs_values,
+        sample_rate: format.sample_rate,
+        channels: format.channels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `fmt `チャンク+任意個の追加チャンク+`data`チャンクを持つ最小のWAVバイト列を組み立てる。
+    /// `extra_chunks`は`(タグ, ボディ)`のリストで、`fmt `の後・`data`の前に順番に挿入される。
+    fn build_wav(
+        audio_format: u16,
+        channels: u16,
+        sample_rate: u32,
+        bits_per_sample: u16,
+        extra_chunks: &[(&[u8; 4], &[u8])],
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut fmt_body = Vec::new();
+        fmt_body.extend_from_slice(&audio_format.to_le_bytes());
+        fmt_body.extend_from_slice(&channels.to_le_bytes());
+        fmt_body.extend_from_slice(&sample_rate.to_le_bytes());
+        let byte_rate = sample_rate * channels as u32 * (bits_per_sample as u32 / 8);
+        fmt_body.extend_from_slice(&byte_rate.to_le_bytes());
+        let block_align = channels * (bits_per_sample / 8);
+        fmt_body.extend_from_slice(&block_align.to_le_bytes());
+        fmt_body.extend_from_slice(&bits_per_sample.to_le_bytes());
+
+        let mut chunks = Vec::new();
+        chunks.push((b"fmt ", fmt_body.as_slice()));
+        chunks.extend_from_slice(extra_chunks);
+        chunks.push((b"data", data));
+
+        let mut body = Vec::new();
+        for (tag, chunk_body) in &chunks {
+            body.extend_from_slice(*tag);
+            body.extend_from_slice(&(chunk_body.len() as u32).to_le_bytes());
+            body.extend_from_slice(chunk_body);
+            if chunk_body.len() % 2 == 1 {
+                body.push(0);
+            }
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&((body.len() + 4) as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(&body);
+        bytes
+    }
+
+    #[test]
+    fn decodes_8bit_pcm_to_expected_range() {
+        let data = [0u8, 128, 255]; // 最小/中央/最大 -> -1.0 / 0.0 / ほぼ1.0
+        let wav = build_wav(WAVE_FORMAT_PCM, 1, 44100, 8, &[], &data);
+        let (format, body) = parse_chunks(&wav).unwrap();
+        let samples = decode_samples(&format, body).unwrap();
+
+        assert!((samples[0] - (-1.0)).abs() < 1.0e-6);
+        assert!((samples[1] - 0.0).abs() < 1.0e-6);
+        assert!((samples[2] - 0.9921875).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn decodes_16bit_pcm_to_expected_range() {
+        let data_samples: [i16; 2] = [i16::MIN, i16::MAX];
+        let mut data = Vec::new();
+        for s in data_samples {
+            data.extend_from_slice(&s.to_le_bytes());
+        }
+        let wav = build_wav(WAVE_FORMAT_PCM, 1, 44100, 16, &[], &data);
+        let (format, body) = parse_chunks(&wav).unwrap();
+        let samples = decode_samples(&format, body).unwrap();
+
+        assert!((samples[0] - (-1.0)).abs() < 1.0e-6);
+        assert!((samples[1] - 32767.0 / 32768.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn decodes_24bit_pcm_with_sign_extension() {
+        // -1 (符号拡張で全ビット1になるはず) と 最大正値
+        let mut data = Vec::new();
+        data.extend_from_slice(&[0xFF, 0xFF, 0xFF]); // -1
+        data.extend_from_slice(&[0xFF, 0xFF, 0x7F]); // 8_388_607 (最大正値)
+        let wav = build_wav(WAVE_FORMAT_PCM, 1, 44100, 24, &[], &data);
+        let (format, body) = parse_chunks(&wav).unwrap();
+        let samples = decode_samples(&format, body).unwrap();
+
+        assert!((samples[0] - (-1.0 / 8_388_608.0)).abs() < 1.0e-9);
+        assert!((samples[1] - 8_388_607.0 / 8_388_608.0).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn decodes_32bit_pcm_to_expected_range() {
+        let data_samples: [i32; 2] = [i32::MIN, i32::MAX];
+        let mut data = Vec::new();
+        for s in data_samples {
+            data.extend_from_slice(&s.to_le_bytes());
+        }
+        let wav = build_wav(WAVE_FORMAT_PCM, 1, 44100, 32, &[], &data);
+        let (format, body) = parse_chunks(&wav).unwrap();
+        let samples = decode_samples(&format, body).unwrap();
+
+        assert!((samples[0] - (-1.0)).abs() < 1.0e-9);
+        assert!((samples[1] - i32::MAX as f32 / 2_147_483_648.0).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn decodes_32bit_float_pcm_unchanged() {
+        let data_samples: [f32; 2] = [-0.5, 0.75];
+        let mut data = Vec::new();
+        for s in data_samples {
+            data.extend_from_slice(&s.to_le_bytes());
+        }
+        let wav = build_wav(WAVE_FORMAT_IEEE_FLOAT, 1, 44100, 32, &[], &data);
+        let (format, body) = parse_chunks(&wav).unwrap();
+        let samples = decode_samples(&format, body).unwrap();
+
+        assert_eq!(samples, vec![-0.5, 0.75]);
+    }
+
+    #[test]
+    fn skips_list_and_junk_chunks_between_fmt_and_data() {
+        let data = [0u8, 128, 255];
+        let wav = build_wav(
+            WAVE_FORMAT_PCM,
+            1,
+            44100,
+            8,
+            &[(b"JUNK", &[0u8; 3]), (b"LIST", b"INFOabc")],
+            &data,
+        );
+
+        let (format, body) = parse_chunks(&wav).unwrap();
+        assert_eq!(format.bits_per_sample, 8);
+        assert_eq!(body, &data);
+    }
+
+    #[test]
+    fn missing_fmt_chunk_is_an_error() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&4u32.to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+
+        assert_eq!(parse_chunks(&bytes).unwrap_err(), WavParseError::MissingFmtChunk);
+    }
+
+    #[test]
+    fn missing_data_chunk_is_an_error() {
+        let fmt_body: [u8; 16] = [1, 0, 1, 0, 0x44, 0xAC, 0, 0, 0x88, 0x58, 1, 0, 2, 0, 16, 0];
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(8 + fmt_body.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&(fmt_body.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&fmt_body);
+
+        assert_eq!(parse_chunks(&bytes).unwrap_err(), WavParseError::MissingDataChunk);
+    }
+
+    #[test]
+    fn oversized_chunk_size_returns_error_instead_of_panicking() {
+        // `0xFFFFFFFF`は「長さ不明」を表す慣習として実在するサイズ値。body_start +
+        // chunk_sizeが32bit usizeで桁あふれしてもパニックせず、エラーを返すべき。
+        let fmt_body: [u8; 16] = [1, 0, 1, 0, 0x44, 0xAC, 0, 0, 0x88, 0x58, 1, 0, 2, 0, 16, 0];
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&0u32.to_le_bytes()); // RIFFサイズは本テストでは使われない
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&(fmt_body.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&fmt_body);
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        bytes.extend_from_slice(&[0u8; 4]); // 実際のdata本体はごく短い
+
+        assert_eq!(parse_chunks(&bytes).unwrap_err(), WavParseError::ChunkOutOfBounds);
+    }
+
+    #[test]
+    fn chunk_body_end_rejects_overflow_and_out_of_range_sizes() {
+        // ネイティブの64bit usizeではオーバーフローしない組み合わせでも、
+        // 実データ範囲を超えていれば拒否されるべき（wasm32の32bit usizeで
+        // 桁あふれする組み合わせと等価な境界条件）
+        assert_eq!(chunk_body_end(44, 0xFFFF_FFFF, 48), None);
+        // 境界ぴったりは許可される
+        assert_eq!(chunk_body_end(44, 4, 48), Some(48));
+        // 1バイトでも超えると拒否される
+        assert_eq!(chunk_body_end(44, 5, 48), None);
+        // usize::MAX近くでのオーバーフローそのものも拒否される
+        assert_eq!(chunk_body_end(usize::MAX - 1, 10, usize::MAX), None);
+    }
+}